@@ -0,0 +1,14 @@
+use futures_core::Stream;
+
+use crate::drf::Request;
+
+use super::reading::Reading;
+use super::Result;
+
+// Fires `req` and yields replies as they arrive, without blocking
+// for the first one — the non-blocking counterpart to `SyncClient`.
+pub trait AsyncClient {
+    type Stream: Stream<Item = Result<Reading>>;
+
+    fn subscribe(&self, req: &Request) -> Self::Stream;
+}
@@ -0,0 +1,23 @@
+use std::fmt;
+
+// Errors a `Transport` can report back to a `SyncClient` or
+// `AsyncClient`. `Transient` is the only variant `SyncClient::read`
+// retries; the others are handed straight back to the caller.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClientError {
+    Transient(String),
+    Connection(String),
+    Decode(String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Transient(msg) => write!(f, "transient transport error: {}", msg),
+            ClientError::Connection(msg) => write!(f, "connection error: {}", msg),
+            ClientError::Decode(msg) => write!(f, "reply decode error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
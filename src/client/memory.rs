@@ -0,0 +1,77 @@
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::drf::Request;
+
+use super::transport::{RawReply, Transport};
+use super::{ClientError, Result};
+
+// An in-memory `Transport`: replies (or errors) are queued up ahead
+// of time with `push_reply`/`push_error` and handed out in order as
+// `recv` is called, so `SyncClient`/`AsyncClient` impls can be
+// exercised without a real data server.
+#[derive(Default)]
+pub struct MemoryTransport {
+    replies: Mutex<Vec<Result<RawReply>>>,
+}
+
+impl MemoryTransport {
+    pub fn new() -> Self {
+        MemoryTransport::default()
+    }
+
+    // Queues `bytes` as the payload of the next `recv()` call.
+    pub fn push_reply(&self, bytes: Vec<u8>) {
+        self.replies
+            .lock()
+            .unwrap()
+            .push(Ok(RawReply { stamp: SystemTime::now(), bytes }));
+    }
+
+    // Queues an error for the next `recv()` call, e.g. to exercise
+    // `SyncClient::read`'s retry path.
+    pub fn push_error(&self, err: ClientError) {
+        self.replies.lock().unwrap().push(Err(err));
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, _request: &Request) -> Result<()> {
+        Ok(())
+    }
+
+    fn recv(&self) -> Result<RawReply> {
+        let mut replies = self.replies.lock().unwrap();
+
+        if replies.is_empty() {
+            return Err(ClientError::Connection(String::from(
+                "MemoryTransport: no queued reply",
+            )));
+        }
+
+        replies.remove(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replies_are_handed_out_in_order() {
+        let transport = MemoryTransport::new();
+
+        transport.push_reply(vec![1]);
+        transport.push_reply(vec![2]);
+
+        assert_eq!(transport.recv().unwrap().bytes, vec![1]);
+        assert_eq!(transport.recv().unwrap().bytes, vec![2]);
+    }
+
+    #[test]
+    fn test_recv_with_nothing_queued_is_an_error() {
+        let transport = MemoryTransport::new();
+
+        assert!(transport.recv().is_err());
+    }
+}
@@ -0,0 +1,24 @@
+// Talks to an accelerator data server on behalf of a parsed `Request`.
+// `drf` only ever builds and validates DRF strings; this module is
+// what actually opens a connection, submits them, and turns the
+// replies into `Reading`s.
+
+mod async_client;
+mod error;
+mod memory;
+mod reading;
+mod schedule;
+mod sync_client;
+mod transport;
+
+pub use async_client::AsyncClient;
+pub use error::ClientError;
+pub use memory::MemoryTransport;
+pub use reading::{Reading, ReadingValue};
+pub use schedule::{
+    subscribe, CancelHandle, ClockSource, EngineContext, EventStream, StateSource, TimedSample,
+};
+pub use sync_client::SyncClient;
+pub use transport::{RawReply, Transport};
+
+pub type Result<T> = std::result::Result<T, ClientError>;
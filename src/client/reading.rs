@@ -0,0 +1,149 @@
+use std::convert::TryInto;
+use std::time::SystemTime;
+
+use crate::drf::{Device, Range, ReadingField};
+
+use super::transport::RawReply;
+use super::{ClientError, Result};
+
+// A decoded reply to a `Request`. `value` honors whichever `Range`
+// the request asked for: `Range::Array` and `Range::Full` decode as
+// floating point samples, `Range::Raw` is handed back untouched.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReadingValue {
+    Scalar(f64),
+    Array(Vec<f64>),
+    Raw(Vec<u8>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reading {
+    pub device: Device,
+    pub stamp: SystemTime,
+    pub value: ReadingValue,
+    pub field: ReadingField,
+}
+
+impl Reading {
+    // Decodes `reply` into a `Reading`, letting `range` pick between
+    // a scalar, an array slice, or a raw byte payload.
+    pub(crate) fn decode(
+        device: Device,
+        field: ReadingField,
+        range: &Range,
+        reply: RawReply,
+    ) -> Result<Self> {
+        let value = match range {
+            Range::Raw { .. } => ReadingValue::Raw(reply.bytes),
+
+            Range::Array { start_index, end_index } => {
+                let samples = decode_f64s(&reply.bytes)?;
+                let start = *start_index as usize;
+                let end = end_index.map(|e| e as usize + 1).unwrap_or(samples.len());
+
+                ReadingValue::Array(samples.get(start..end).unwrap_or(&[]).to_vec())
+            }
+
+            Range::Full => match decode_f64s(&reply.bytes)?.as_slice() {
+                [v] => ReadingValue::Scalar(*v),
+                samples => ReadingValue::Array(samples.to_vec()),
+            },
+        };
+
+        Ok(Reading { device, stamp: reply.stamp, value, field })
+    }
+}
+
+fn decode_f64s(bytes: &[u8]) -> Result<Vec<f64>> {
+    if !bytes.len().is_multiple_of(8) {
+        return Err(ClientError::Decode(format!(
+            "reply length {} is not a multiple of 8 bytes", bytes.len()
+        )));
+    }
+
+    Ok(bytes
+        .chunks_exact(8)
+        .map(|c| f64::from_le_bytes(c.try_into().unwrap()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::drf::Request;
+
+    use super::*;
+
+    fn device() -> Device {
+        Request::from_str("M:OUTTMP.READING.SCALED").unwrap().device
+    }
+
+    fn reply(bytes: Vec<u8>) -> RawReply {
+        RawReply { stamp: SystemTime::now(), bytes }
+    }
+
+    fn floats_to_bytes(samples: &[f64]) -> Vec<u8> {
+        samples.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_decode_full_range_single_sample_is_scalar() {
+        let reading = Reading::decode(
+            device(), ReadingField::Scaled, &Range::Full, reply(floats_to_bytes(&[2.5])),
+        ).unwrap();
+
+        assert_eq!(reading.value, ReadingValue::Scalar(2.5));
+    }
+
+    #[test]
+    fn test_decode_full_range_multiple_samples_is_array() {
+        let reading = Reading::decode(
+            device(), ReadingField::Scaled, &Range::Full,
+            reply(floats_to_bytes(&[1.0, 2.0])),
+        ).unwrap();
+
+        assert_eq!(reading.value, ReadingValue::Array(vec![1.0, 2.0]));
+    }
+
+    #[test]
+    fn test_decode_array_range_slices_samples() {
+        let range = Range::Array { start_index: 1, end_index: Some(2) };
+        let reading = Reading::decode(
+            device(), ReadingField::Scaled, &range,
+            reply(floats_to_bytes(&[1.0, 2.0, 3.0, 4.0])),
+        ).unwrap();
+
+        assert_eq!(reading.value, ReadingValue::Array(vec![2.0, 3.0]));
+    }
+
+    #[test]
+    fn test_decode_array_range_open_ended_takes_the_rest() {
+        let range = Range::Array { start_index: 2, end_index: None };
+        let reading = Reading::decode(
+            device(), ReadingField::Scaled, &range,
+            reply(floats_to_bytes(&[1.0, 2.0, 3.0, 4.0])),
+        ).unwrap();
+
+        assert_eq!(reading.value, ReadingValue::Array(vec![3.0, 4.0]));
+    }
+
+    #[test]
+    fn test_decode_raw_range_passes_bytes_through() {
+        let range = Range::Raw { offset: 0, length: Some(3) };
+        let reading = Reading::decode(
+            device(), ReadingField::Raw, &range, reply(vec![1, 2, 3]),
+        ).unwrap();
+
+        assert_eq!(reading.value, ReadingValue::Raw(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_decode_rejects_payload_not_a_multiple_of_8_bytes() {
+        let err = Reading::decode(
+            device(), ReadingField::Scaled, &Range::Full, reply(vec![0, 1, 2]),
+        ).unwrap_err();
+
+        assert!(matches!(err, ClientError::Decode(_)));
+    }
+}
@@ -0,0 +1,403 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use futures_core::Stream;
+
+use crate::drf::{ClockType, Event, Request};
+
+use super::reading::Reading;
+use super::{ClientError, Result};
+
+// How many samples the schedule thread may get ahead of a slow
+// consumer before it blocks. Blocking the producer rather than
+// buffering without bound is the engine's backpressure mechanism.
+const CHANNEL_CAPACITY: usize = 1;
+
+// One acquisition produced by the event engine, timestamped so
+// periodic and clock-triggered samples stay correctly ordered even
+// if the consumer falls behind.
+#[derive(Clone, Debug)]
+pub struct TimedSample {
+    pub stamp: SystemTime,
+    pub reading: Result<Reading>,
+}
+
+// Supplies the moment a named hardware/software clock event fires,
+// letting `Event::Clock` turn into an actual wake-up instead of just
+// a string.
+pub trait ClockSource: Send + Sync {
+    fn wait_for(&self, event: u16, clk_type: ClockType) -> Result<()>;
+}
+
+// Supplies another device's current value, letting `Event::State`
+// evaluate its `StateOp` comparison.
+pub trait StateSource: Send + Sync {
+    fn value(&self, device: u32) -> Result<u16>;
+}
+
+// How a schedule fetches a reading once it decides it's time to
+// sample.
+pub type AcquireFn = dyn Fn(&Request) -> Result<Reading> + Send + Sync;
+
+// The pieces of the outside world a schedule needs: how to fetch a
+// reading for the subscribed request, and (only when the request
+// needs them) how to watch a clock or another device's state.
+pub struct EngineContext {
+    pub acquire: Box<AcquireFn>,
+    pub clock: Option<Arc<dyn ClockSource>>,
+    pub state: Option<Arc<dyn StateSource>>,
+}
+
+// Stops the schedule thread started by `subscribe`. Dropping the
+// `EventStream` alone stops it only at its next attempt to send a
+// sample; a schedule waiting on a clock or a device state, or
+// suppressing duplicates, keeps running until cancelled.
+#[derive(Clone)]
+pub struct CancelHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelHandle {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+}
+
+type WakerSlot = Arc<Mutex<Option<Waker>>>;
+
+// A `Stream` of `TimedSample`s driven by a schedule thread that
+// turns `req.event` into real wake-ups.
+pub struct EventStream {
+    rx: mpsc::Receiver<TimedSample>,
+    waker: WakerSlot,
+}
+
+impl Stream for EventStream {
+    type Item = TimedSample;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.rx.try_recv() {
+            Ok(sample) => return Poll::Ready(Some(sample)),
+            Err(mpsc::TryRecvError::Disconnected) => return Poll::Ready(None),
+            Err(mpsc::TryRecvError::Empty) => {}
+        }
+
+        *self.waker.lock().unwrap() = Some(cx.waker().clone());
+
+        // The schedule thread may have sent a sample between the
+        // first `try_recv` and the waker being stored above, in
+        // which case its wake-up call found no waker and did
+        // nothing. Re-check now that the waker is in place so that
+        // sample isn't stranded until some later, unrelated wake-up.
+        match self.rx.try_recv() {
+            Ok(sample) => Poll::Ready(Some(sample)),
+            Err(mpsc::TryRecvError::Disconnected) => Poll::Ready(None),
+            Err(mpsc::TryRecvError::Empty) => Poll::Pending,
+        }
+    }
+}
+
+// Turns `req.event` into an actual sampling schedule: `Periodic`
+// emits on a fixed interval, `Clock` waits for a named clock event,
+// and `State` waits for another device's value to satisfy its
+// comparison. Returns the resulting stream alongside a handle that
+// cancels the schedule thread.
+pub fn subscribe(req: Request, ctx: EngineContext) -> (EventStream, CancelHandle) {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let handle = CancelHandle { cancelled: cancelled.clone() };
+    let waker: WakerSlot = Arc::new(Mutex::new(None));
+    let (tx, rx) = mpsc::sync_channel(CHANNEL_CAPACITY);
+
+    {
+        let cancelled = cancelled.clone();
+        let waker = waker.clone();
+
+        thread::spawn(move || run_schedule(req, ctx, tx, cancelled, waker));
+    }
+
+    (EventStream { rx, waker }, handle)
+}
+
+fn wake(waker: &WakerSlot) {
+    if let Some(w) = waker.lock().unwrap().take() {
+        w.wake();
+    }
+}
+
+// Sends `reading` to the stream, stamped with the current time.
+// Returns `false` once the receiving `EventStream` has been dropped,
+// telling the caller to stop scheduling further samples.
+fn emit(tx: &SyncSender<TimedSample>, waker: &WakerSlot, reading: Result<Reading>) -> bool {
+    let sample = TimedSample { stamp: SystemTime::now(), reading };
+    let sent = tx.send(sample).is_ok();
+
+    wake(waker);
+    sent
+}
+
+fn run_schedule(
+    req: Request,
+    ctx: EngineContext,
+    tx: SyncSender<TimedSample>,
+    cancelled: Arc<AtomicBool>,
+    waker: WakerSlot,
+) {
+    match req.event {
+        Event::Never => {}
+
+        Event::Immediate | Event::Default => {
+            emit(&tx, &waker, (ctx.acquire)(&req));
+        }
+
+        Event::Periodic { period, immediate, skip_dups } => {
+            let interval = Duration::from_micros(period as u64);
+            let mut last: Option<Reading> = None;
+            let mut first = true;
+
+            while !cancelled.load(Ordering::SeqCst) {
+                if first && !immediate {
+                    first = false;
+                    thread::sleep(interval);
+                    continue;
+                }
+                first = false;
+
+                let reading = (ctx.acquire)(&req);
+                let is_dup = skip_dups
+                    && matches!((&reading, &last), (Ok(r), Some(p)) if r.value == p.value);
+
+                if !is_dup {
+                    if let Ok(r) = &reading {
+                        last = Some(r.clone());
+                    }
+                    if !emit(&tx, &waker, reading) {
+                        break;
+                    }
+                }
+
+                thread::sleep(interval);
+            }
+        }
+
+        Event::Clock { event, clk_type, delay } => {
+            let Some(clock) = ctx.clock.clone() else {
+                emit(&tx, &waker, Err(ClientError::Connection(String::from(
+                    "Event::Clock requires an EngineContext.clock source"
+                ))));
+                return;
+            };
+
+            while !cancelled.load(Ordering::SeqCst) {
+                if clock.wait_for(event, clk_type).is_err() {
+                    break;
+                }
+                if delay > 0 {
+                    thread::sleep(Duration::from_micros(delay as u64));
+                }
+                if !emit(&tx, &waker, (ctx.acquire)(&req)) {
+                    break;
+                }
+            }
+        }
+
+        Event::State { device, value, delay, expr } => {
+            let Some(state) = ctx.state.clone() else {
+                emit(&tx, &waker, Err(ClientError::Connection(String::from(
+                    "Event::State requires an EngineContext.state source"
+                ))));
+                return;
+            };
+            // Polling interval while waiting for the watched
+            // device's value to satisfy `expr`.
+            let poll_interval = Duration::from_millis(10);
+            // DRF state events are edge-triggered: a sample is taken
+            // on the transition into the satisfied state, not on
+            // every poll that finds it still satisfied.
+            let mut was_satisfied = false;
+
+            while !cancelled.load(Ordering::SeqCst) {
+                match state.value(device) {
+                    Ok(current) if expr.matches(current, value) => {
+                        if was_satisfied {
+                            thread::sleep(poll_interval);
+                            continue;
+                        }
+                        was_satisfied = true;
+
+                        if delay > 0 {
+                            thread::sleep(Duration::from_micros(delay as u64));
+                        }
+                        if !emit(&tx, &waker, (ctx.acquire)(&req)) {
+                            break;
+                        }
+                    }
+                    Ok(_) => {
+                        was_satisfied = false;
+                        thread::sleep(poll_interval);
+                    }
+                    Err(_) => break,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::atomic::AtomicU32;
+
+    use futures_core::Stream;
+
+    use super::*;
+    use crate::client::ReadingValue;
+    use crate::drf::ReadingField;
+
+    fn request() -> Request {
+        Request::from_str("M:OUTTMP.READING.SCALED").unwrap()
+    }
+
+    // Polls `stream` until it yields a sample or `attempts` polls
+    // have gone by without one, since the engine runs on its own
+    // thread and a sample may not be ready the instant we look.
+    fn next_sample(stream: &mut EventStream, attempts: u32) -> Option<TimedSample> {
+        use std::task::{RawWaker, RawWakerVTable, Waker};
+
+        fn noop(_: *const ()) {}
+        fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        let waker = unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) };
+        let mut cx = Context::from_waker(&waker);
+
+        for _ in 0..attempts {
+            match Pin::new(&mut *stream).poll_next(&mut cx) {
+                Poll::Ready(sample) => return sample,
+                Poll::Pending => thread::sleep(Duration::from_millis(5)),
+            }
+        }
+        None
+    }
+
+    fn acquire_ok() -> Box<AcquireFn> {
+        Box::new(|req| {
+            Ok(Reading {
+                device: req.device.clone(),
+                stamp: SystemTime::now(),
+                value: ReadingValue::Scalar(1.0),
+                field: ReadingField::Scaled,
+            })
+        })
+    }
+
+    fn engine_context(acquire: Box<AcquireFn>) -> EngineContext {
+        EngineContext { acquire, clock: None, state: None }
+    }
+
+    #[test]
+    fn test_periodic_immediate_emits_without_waiting_a_full_period() {
+        let req = Request {
+            event: Event::Periodic { period: 60_000_000, immediate: true, skip_dups: false },
+            ..request()
+        };
+        let (mut stream, handle) = subscribe(req, engine_context(acquire_ok()));
+
+        let sample = next_sample(&mut stream, 20).expect("expected an immediate sample");
+
+        assert_eq!(sample.reading.unwrap().value, ReadingValue::Scalar(1.0));
+        handle.cancel();
+    }
+
+    #[test]
+    fn test_periodic_skip_dups_suppresses_repeated_value() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let calls_for_acquire = calls.clone();
+        let acquire: Box<AcquireFn> = Box::new(move |req| {
+            calls_for_acquire.fetch_add(1, Ordering::SeqCst);
+            Ok(Reading {
+                device: req.device.clone(),
+                stamp: SystemTime::now(),
+                value: ReadingValue::Scalar(1.0),
+                field: ReadingField::Scaled,
+            })
+        });
+        let req = Request {
+            event: Event::Periodic { period: 5_000, immediate: true, skip_dups: true },
+            ..request()
+        };
+        let (mut stream, handle) = subscribe(req, engine_context(acquire));
+
+        let first = next_sample(&mut stream, 40).expect("expected the first sample");
+        assert_eq!(first.reading.unwrap().value, ReadingValue::Scalar(1.0));
+
+        // Every later acquisition returns the same value, so no
+        // further sample should ever reach the stream.
+        assert!(next_sample(&mut stream, 20).is_none());
+        handle.cancel();
+    }
+
+    #[test]
+    fn test_cancel_handle_stops_the_schedule_thread() {
+        let req = Request {
+            event: Event::Periodic { period: 5_000, immediate: true, skip_dups: false },
+            ..request()
+        };
+        let (mut stream, handle) = subscribe(req, engine_context(acquire_ok()));
+
+        next_sample(&mut stream, 40).expect("expected at least one sample");
+        handle.cancel();
+
+        // Once cancelled the schedule thread exits and drops `tx`,
+        // so draining the channel should reach a disconnect instead
+        // of the thread emitting forever.
+        let mut saw_disconnect = false;
+        for _ in 0..200 {
+            match stream.rx.try_recv() {
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    saw_disconnect = true;
+                    break;
+                }
+                _ => thread::sleep(Duration::from_millis(5)),
+            }
+        }
+        assert!(saw_disconnect);
+    }
+
+    struct StubState(AtomicU32);
+
+    impl StateSource for StubState {
+        fn value(&self, _device: u32) -> Result<u16> {
+            Ok(self.0.load(Ordering::SeqCst) as u16)
+        }
+    }
+
+    #[test]
+    fn test_state_event_is_edge_triggered() {
+        use crate::drf::StateOp;
+
+        let state = Arc::new(StubState(AtomicU32::new(0)));
+        let req = Request {
+            event: Event::State { device: 1, value: 1, delay: 0, expr: StateOp::Eq },
+            ..request()
+        };
+        let ctx = EngineContext { acquire: acquire_ok(), clock: None, state: Some(state.clone()) };
+        let (mut stream, handle) = subscribe(req, ctx);
+
+        // Not yet satisfied: no sample should show up.
+        assert!(next_sample(&mut stream, 10).is_none());
+
+        // Transition into the satisfied state emits exactly one
+        // sample, not a continuous stream of them.
+        state.0.store(1, Ordering::SeqCst);
+        next_sample(&mut stream, 40).expect("expected a sample on the transition");
+        assert!(next_sample(&mut stream, 20).is_none());
+
+        handle.cancel();
+    }
+}
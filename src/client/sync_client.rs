@@ -0,0 +1,112 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::drf::{Property, ReadingField, Request};
+
+use super::reading::Reading;
+use super::transport::Transport;
+use super::{ClientError, Result};
+
+// Number of times `read` retries a `ClientError::Transient` failure
+// before giving up and returning it to the caller.
+const MAX_RETRIES: u32 = 3;
+
+// Opens a connection, submits `req.canonical()`, and blocks for one
+// reply, retrying transient transport errors along the way.
+pub trait SyncClient {
+    fn transport(&self) -> &dyn Transport;
+
+    fn read(&self, req: &Request) -> Result<Reading> {
+        let mut attempt = 0;
+
+        loop {
+            let outcome = self
+                .transport()
+                .send(req)
+                .and_then(|_| self.transport().recv());
+
+            match outcome {
+                Ok(reply) => {
+                    return Reading::decode(
+                        req.device.clone(),
+                        reading_field(req),
+                        &req.range,
+                        reply,
+                    );
+                }
+                Err(ClientError::Transient(_)) if attempt < MAX_RETRIES => {
+                    attempt += 1;
+                    thread::sleep(Duration::from_millis(50 * attempt as u64));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+fn reading_field(req: &Request) -> ReadingField {
+    match req.property {
+        Property::Reading(field) => field,
+        _ => ReadingField::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::client::memory::MemoryTransport;
+    use crate::client::ReadingValue;
+
+    struct TestClient<'a>(&'a MemoryTransport);
+
+    impl SyncClient for TestClient<'_> {
+        fn transport(&self) -> &dyn Transport {
+            self.0
+        }
+    }
+
+    fn request() -> Request {
+        Request::from_str("M:OUTTMP.READING.SCALED").unwrap()
+    }
+
+    #[test]
+    fn test_read_retries_transient_errors_then_succeeds() {
+        let transport = MemoryTransport::new();
+
+        transport.push_error(ClientError::Transient(String::from("timeout")));
+        transport.push_error(ClientError::Transient(String::from("timeout")));
+        transport.push_reply(1.5f64.to_le_bytes().to_vec());
+
+        let reading = TestClient(&transport).read(&request()).unwrap();
+
+        // The request carries no range, i.e. `Range::Array` over
+        // element 0 alone, so the reply decodes as a one-element array.
+        assert_eq!(reading.value, ReadingValue::Array(vec![1.5]));
+    }
+
+    #[test]
+    fn test_read_gives_up_after_max_retries() {
+        let transport = MemoryTransport::new();
+
+        for _ in 0..=MAX_RETRIES {
+            transport.push_error(ClientError::Transient(String::from("timeout")));
+        }
+
+        let err = TestClient(&transport).read(&request()).unwrap_err();
+
+        assert!(matches!(err, ClientError::Transient(_)));
+    }
+
+    #[test]
+    fn test_read_returns_non_transient_errors_immediately() {
+        let transport = MemoryTransport::new();
+
+        transport.push_error(ClientError::Connection(String::from("refused")));
+
+        let err = TestClient(&transport).read(&request()).unwrap_err();
+
+        assert!(matches!(err, ClientError::Connection(_)));
+    }
+}
@@ -0,0 +1,24 @@
+use std::time::SystemTime;
+
+use crate::drf::Request;
+
+use super::Result;
+
+// One reply frame from the data server, not yet decoded into a
+// `Reading`. Decoding happens in `reading.rs` once the caller knows
+// which `Range`/`*Field` combination the request asked for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RawReply {
+    pub stamp: SystemTime,
+    pub bytes: Vec<u8>,
+}
+
+// The blocking, byte-level half of talking to a data server: submit
+// the canonical DRF string for a request and read back whatever
+// replies arrive. `SyncClient` is built on top of this so a TCP/ACNET
+// backend and an in-memory test backend can be swapped in without
+// touching client logic; `AsyncClient` does not reference it.
+pub trait Transport: Send + Sync {
+    fn send(&self, request: &Request) -> Result<()>;
+    fn recv(&self) -> Result<RawReply>;
+}
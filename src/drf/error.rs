@@ -0,0 +1,149 @@
+use std::fmt;
+
+use super::{
+    AnalogField, DigitalField, Property, ReadingField, SettingField, StatusField,
+};
+
+// One instance of each `Property` variant, used only to read back
+// its canonical property marker (the field each carries is
+// irrelevant here).
+const PROPERTIES: [Property; 10] = [
+    Property::Reading(ReadingField::Scaled),
+    Property::Setting(SettingField::Scaled),
+    Property::Status(StatusField::All),
+    Property::Control,
+    Property::Analog(AnalogField::All),
+    Property::Digital(DigitalField::All),
+    Property::Description,
+    Property::Index,
+    Property::LongName,
+    Property::AlarmList,
+];
+
+const READING_FIELDS: [ReadingField; 3] =
+    [ReadingField::Raw, ReadingField::Primary, ReadingField::Scaled];
+
+const SETTING_FIELDS: [SettingField; 3] =
+    [SettingField::Raw, SettingField::Primary, SettingField::Scaled];
+
+const STATUS_FIELDS: [StatusField; 9] = [
+    StatusField::Raw, StatusField::All, StatusField::Text, StatusField::ExtText,
+    StatusField::On, StatusField::Ready, StatusField::Remote, StatusField::Positive,
+    StatusField::Ramp,
+];
+
+const ANALOG_FIELDS: [AnalogField; 19] = [
+    AnalogField::Raw, AnalogField::All, AnalogField::Text, AnalogField::Min,
+    AnalogField::Max, AnalogField::Nom, AnalogField::Tol, AnalogField::RawMin,
+    AnalogField::RawMax, AnalogField::RawNom, AnalogField::RawTol, AnalogField::Enable,
+    AnalogField::Status, AnalogField::TriesNeeded, AnalogField::TriesNow, AnalogField::FTD,
+    AnalogField::Abort, AnalogField::AbortInhibit, AnalogField::Flags,
+];
+
+const DIGITAL_FIELDS: [DigitalField; 13] = [
+    DigitalField::Raw, DigitalField::All, DigitalField::Text, DigitalField::Nom,
+    DigitalField::Mask, DigitalField::Enable, DigitalField::Status, DigitalField::TriesNeeded,
+    DigitalField::TriesNow, DigitalField::FTD, DigitalField::Abort, DigitalField::AbortInhibit,
+    DigitalField::Flags,
+];
+
+// Identifies which of the sub-parsers invoked by `parse()` rejected
+// the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    Device,
+    Property,
+    Field,
+    Range,
+    Event,
+}
+
+impl Stage {
+    fn label(&self) -> &'static str {
+        match *self {
+            Stage::Device => "device",
+            Stage::Property => "property",
+            Stage::Field => "field",
+            Stage::Range => "range",
+            Stage::Event => "event",
+        }
+    }
+
+    // The canonical tokens the stage's sub-parser accepts, drawn
+    // from the same enums `parse()` eventually builds, rather than
+    // a separately maintained copy of the grammar.
+    fn expected_tokens(&self) -> Vec<&'static str> {
+        match *self {
+            Stage::Device => vec!["a device mnemonic"],
+            Stage::Property => PROPERTIES.iter().map(|p| p.canonical().0).collect(),
+            Stage::Field => READING_FIELDS.iter().map(|f| f.canonical())
+                .chain(SETTING_FIELDS.iter().map(|f| f.canonical()))
+                .chain(STATUS_FIELDS.iter().map(|f| f.canonical()))
+                .chain(ANALOG_FIELDS.iter().map(|f| f.canonical()))
+                .chain(DIGITAL_FIELDS.iter().map(|f| f.canonical()))
+                .collect(),
+            Stage::Range => vec!["[", "{"],
+            Stage::Event => vec!["@N", "@I", "@P", "@Q", "@E", "@S"],
+        }
+    }
+}
+
+// A structured parse failure, pinpointing the stage and character
+// offset at which `parse_drf` rejected its input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DrfParseError {
+    pub offset: usize,
+    pub stage: Stage,
+    pub found: Option<char>,
+    pub expected: Vec<&'static str>,
+}
+
+impl DrfParseError {
+    pub(crate) fn new(offset: usize, stage: Stage, found: Option<char>) -> Self {
+        DrfParseError {
+            offset,
+            stage,
+            found,
+            expected: stage.expected_tokens(),
+        }
+    }
+
+    // Renders `input` on one line with a caret under the offending
+    // character, followed by the stage name and the alternatives
+    // that would have been accepted there.
+    pub fn render(&self, input: &str) -> String {
+        let caret_line = format!("{}^", " ".repeat(self.offset));
+        let found = match self.found {
+            Some(c) => format!("'{}'", c),
+            None => String::from("end of input"),
+        };
+
+        format!(
+            "{}\n{}\n{} stage: unexpected {}, expected one of: {}",
+            input,
+            caret_line,
+            self.stage.label(),
+            found,
+            self.expected.join(", ")
+        )
+    }
+}
+
+impl fmt::Display for DrfParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.found {
+            Some(c) => write!(
+                f,
+                "{} stage: unexpected '{}' at offset {}",
+                self.stage.label(), c, self.offset
+            ),
+            None => write!(
+                f,
+                "{} stage: unexpected end of input at offset {}",
+                self.stage.label(), self.offset
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DrfParseError {}
@@ -1,5 +1,11 @@
-use combine::{error::StringStreamError, Stream, ParseError, attempt, optional,
-              Parser};
+use std::fmt;
+use std::str::FromStr;
+
+use combine::{Stream, ParseError, attempt, optional, Parser};
+
+mod error;
+
+pub use error::{DrfParseError, Stage};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Device(String);
@@ -194,6 +200,14 @@ impl Property {
     }
 }
 
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (prop, field) = self.canonical();
+
+        write!(f, "{}{}", prop, field)
+    }
+}
+
 // Type which specifies a range of data.
 
 #[derive(Clone, Debug, PartialEq)]
@@ -240,6 +254,12 @@ impl Range {
     }
 }
 
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.canonical())
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum StateOp {
     Eq,
@@ -252,6 +272,21 @@ pub enum StateOp {
 }
 
 impl StateOp {
+    // Evaluates the comparison this op represents between a
+    // device's `current` value and the `target` value from an
+    // `Event::State`.
+    pub fn matches(&self, current: u16, target: u16) -> bool {
+        match *self {
+            StateOp::Eq => current == target,
+            StateOp::NEq => current != target,
+            StateOp::GT => current > target,
+            StateOp::LT => current < target,
+            StateOp::LEq => current <= target,
+            StateOp::GEq => current >= target,
+            StateOp::All => true,
+        }
+    }
+
     pub fn canonical(&self) -> &'static str {
         match *self {
             StateOp::Eq => "=",
@@ -352,6 +387,12 @@ impl Event {
     }
 }
 
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.canonical())
+    }
+}
+
 pub struct Request {
     pub device: Device,
     pub property: Property,
@@ -374,6 +415,42 @@ impl Request {
     }
 }
 
+impl fmt::Display for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.canonical())
+    }
+}
+
+impl FromStr for Request {
+    type Err = DrfParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_drf(s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Request {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.canonical())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Request {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+
+        Request::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
 mod device;
 mod event;
 mod prop_field;
@@ -406,11 +483,76 @@ where
     })
 }
 
-pub fn parse_drf(drf: &str) -> Result<Request, StringStreamError> {
+pub fn parse_drf(drf: &str) -> Result<Request, DrfParseError> {
     match parse().parse(drf) {
         Ok((result, "")) => Ok(result),
-        Ok(_) => Err(StringStreamError::UnexpectedParse),
-        Err(e) => Err(e)
+        _ => Err(diagnose(drf)),
+    }
+}
+
+// Re-runs the same sub-parsers `parse()` chains together, one at a
+// time, to find which stage first rejected the input and at what
+// offset. `parse()` only reports success or failure as a whole, so
+// this walks the same sequence to recover the detail a caller needs
+// to point at the mistake.
+//
+// Property and field are both optional and both introduced by a
+// leading '.', so a parser failure there doesn't necessarily mean
+// that's the wrong stage: `M|OUTTMP.On` has no property segment at
+// all, just a field on the default property, and the property
+// attempt is *expected* to fail before field succeeds. Only once
+// both the property and field attempts have failed on the same
+// dot-segment do we know the input is genuinely broken there, and we
+// blame whichever of the two was tried against that segment first.
+fn diagnose(drf: &str) -> DrfParseError {
+    let offset_of = |rest: &str| drf.len() - rest.len();
+
+    let (qual_property, rest) = match device::parser().parse(drf) {
+        Ok(((_, qual_property), rest)) => (qual_property, rest),
+        Err(_) => return DrfParseError::new(0, Stage::Device, drf.chars().next()),
+    };
+
+    let mut property_matched = false;
+    let (property, rest) = if rest.starts_with('.') {
+        match attempt(prop_field::parse_property(qual_property)).parse(rest) {
+            Ok((p, rest)) => {
+                property_matched = true;
+                (p, rest)
+            }
+            Err(_) => (qual_property, rest),
+        }
+    } else {
+        (qual_property, rest)
+    };
+
+    let rest = match range::parser().parse(rest) {
+        Ok((_, rest)) => rest,
+        Err(_) => return DrfParseError::new(offset_of(rest), Stage::Range, rest.chars().next()),
+    };
+
+    let rest = if rest.starts_with('.') {
+        match attempt(prop_field::parse_field(property)).parse(rest) {
+            Ok((_, rest)) => rest,
+            Err(_) => {
+                let stage = if property_matched { Stage::Field } else { Stage::Property };
+
+                return DrfParseError::new(offset_of(rest), stage, rest.chars().next());
+            }
+        }
+    } else {
+        rest
+    };
+
+    match event::parser().parse(rest) {
+        Ok((_, "")) => {
+            // Every stage matched and consumed the whole string, so
+            // `parse()` should have succeeded too. `diagnose` is
+            // only called when it didn't, so this shouldn't happen;
+            // report the end of input, charged to the last stage.
+            DrfParseError::new(drf.len(), Stage::Event, None)
+        }
+        Ok((_, rest)) => DrfParseError::new(offset_of(rest), Stage::Event, rest.chars().next()),
+        Err(_) => DrfParseError::new(offset_of(rest), Stage::Event, rest.chars().next()),
     }
 }
 
@@ -485,4 +627,45 @@ mod tests {
                        "\n input: {}", drf)
         }
     }
+
+    #[test]
+    fn test_display_from_str_roundtrip() {
+        let data = &[
+            ("M:OUTTMP", "M:OUTTMP.READING.SCALED"),
+            ("M:OUTTMP[0:3]", "M:OUTTMP.READING[0:3].SCALED"),
+            ("M|OUTTMP[]", "M:OUTTMP.STATUS[].ALL"),
+            ("M|OUTTMP[]@e,02", "M:OUTTMP.STATUS[].ALL@E,2,E,0"),
+            ("M|OUTTMP.STATUS[]@e,02", "M:OUTTMP.STATUS[].ALL@E,2,E,0"),
+            ("M|OUTTMP.On@e,02", "M:OUTTMP.STATUS.ON@E,2,E,0"),
+        ];
+
+        for &(drf, result) in data {
+            let req: Request = drf.parse().unwrap();
+
+            assert_eq!(req.to_string(), result, "\n input: {}", drf)
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() {
+        let data = &[
+            "M:OUTTMP.READING.SCALED",
+            "M:OUTTMP.READING[0:3].SCALED",
+            "M:OUTTMP.STATUS[].ALL",
+            "M:OUTTMP.STATUS[].ALL@E,2,E,0",
+            "M:OUTTMP.STATUS.ON@E,2,E,0",
+        ];
+
+        for &canonical in data {
+            let req: Request = canonical.parse().unwrap();
+            let json = serde_json::to_string(&req).unwrap();
+
+            assert_eq!(json, format!("\"{}\"", canonical), "\n input: {}", canonical);
+
+            let from_json: Request = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(from_json.canonical(), canonical, "\n input: {}", canonical)
+        }
+    }
 }